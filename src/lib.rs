@@ -1,11 +1,21 @@
-extern crate i2cdev;
-use i2cdev::core::I2CDevice;
-use i2cdev::linux::{
-    LinuxI2CDevice,
-    LinuxI2CError,
-};
-use std::time::{Duration, Instant};
-use std::thread::sleep;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+extern crate embedded_hal;
+#[cfg(feature = "linux")]
+extern crate linux_embedded_hal;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::InputPin;
+use embedded_hal::i2c::I2c;
+
+#[cfg(feature = "linux")]
+use linux_embedded_hal::I2cdev;
+#[cfg(feature = "linux")]
+use linux_embedded_hal::i2cdev::linux::LinuxI2CError;
 
 const PN532_ADDR:           u8 = 0x24;
 
@@ -48,8 +58,9 @@ enum Command {
 }
 
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(unused)]
-enum CardType {
+pub enum CardType {
     IsoTypeA  = 0x00,
     FeliCa212 = 0x01,
     FeliCa424 = 0x02,
@@ -57,20 +68,256 @@ enum CardType {
     Jewel     = 0x04,
 }
 
-pub struct Pn532 {
-    i2c: LinuxI2CDevice,
+// a target found by `auto_poll`, typed by the technology the chip detected it with.
+// see UM0701-02 page 53 for the per-type target data layout.
+#[derive(Debug, Clone)]
+pub enum Target {
+    IsoTypeA {
+        sens_res: [u8; 2],
+        sel_res: u8,
+        uid: Vec<u8>,
+        ats: Option<Vec<u8>>,
+    },
+    IsoTypeB {
+        atqb: Vec<u8>,
+    },
+    FeliCa212 {
+        id: Vec<u8>,
+    },
+    FeliCa424 {
+        id: Vec<u8>,
+    },
+    Jewel {
+        sens_res: [u8; 2],
+        uid: Vec<u8>,
+    },
+}
+
+// everything that can go wrong talking to the chip: either the bus itself faulted, or it
+// answered but not the way the protocol says it should.
+#[derive(Debug)]
+pub enum Error<E> {
+    Transport(E),
+    Nack,
+    OutOfOrder,
+    Timeout,
+    Application(u8),
+    // the chip sent back fewer bytes than the command requires.
+    ShortResponse,
+    // the caller's data doesn't fit in a single information frame, see `send_frame`.
+    PayloadTooLong,
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(e: E) -> Self {
+        Error::Transport(e)
+    }
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Error::Transport(e) => write!(f, "transport error: {:?}", e),
+            Error::Nack => write!(f, "nack"),
+            Error::OutOfOrder => write!(f, "out of order"),
+            Error::Timeout => write!(f, "timeout"),
+            Error::Application(code) => write!(f, "application error 0x{:x}", code),
+            Error::ShortResponse => write!(f, "response shorter than expected"),
+            Error::PayloadTooLong => write!(f, "payload too long for a single frame"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: core::fmt::Debug> std::error::Error for Error<E> {}
+
+// whatever tells us a frame is ready to be read, checked once per status-byte poll. the
+// blind `()` case has no line to check, so it always defers to the status byte itself; a
+// real IRQ pin (active low) lets us skip the bus entirely while the chip has nothing for us.
+pub trait ReadyLine {
+    fn is_ready(&mut self) -> bool;
+}
+
+impl ReadyLine for () {
+    fn is_ready(&mut self) -> bool {
+        true
+    }
+}
+
+// wraps the chip's IRQ pin (active low) so `ReadyLine` can be implemented for it without
+// a blanket impl over the foreign `InputPin` trait, which would conflict with the `()`
+// impl above the moment `embedded-hal` (or anyone downstream) adds one for `()`.
+pub struct Irq<P>(pub P);
+
+impl<P: InputPin> ReadyLine for Irq<P> {
+    fn is_ready(&mut self) -> bool {
+        matches!(self.0.is_low(), Ok(true))
+    }
+}
+
+// how hard to fight for a command before giving up: how many rounds to retry, and how
+// long to back off between them, in milliseconds. on repeated failure we also emit the
+// chip's abort sequence so a wedged command doesn't keep failing every retry the same way.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    backoff_ms: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            backoff_ms: 10,
+        }
+    }
+}
+
+// driver for the NXP PN532, generic over any `embedded-hal` I2C bus and delay source so it
+// can run on Linux via i2cdev as well as bare-metal HALs (rp2040, stm32, nrf, ...) with no
+// `std`.
+pub struct Pn532<I2C, D, IRQ: ReadyLine = ()> {
+    i2c: I2C,
+    address: u8,
+    delay: D,
+    irq: IRQ,
+    retry: RetryPolicy,
 }
 
-impl Pn532 {
-    pub fn open(dev: &str) -> Result<Self, LinuxI2CError> {
-        let i2c = LinuxI2CDevice::new(dev, PN532_ADDR.into())?;
+#[cfg(feature = "linux")]
+impl Pn532<I2cdev, linux_embedded_hal::Delay, ()> {
+    pub fn open(dev: &str) -> Result<Self, Error<LinuxI2CError>> {
+        let i2c = I2cdev::new(dev).map_err(Error::Transport)?;
         Ok(Self {
-            i2c
+            i2c,
+            address: PN532_ADDR,
+            delay: linux_embedded_hal::Delay,
+            irq: (),
+            retry: RetryPolicy::default(),
         })
     }
+}
+
+#[cfg(feature = "linux")]
+impl<IRQ: ReadyLine> Pn532<I2cdev, linux_embedded_hal::Delay, IRQ> {
+    // like `open`, but with the chip's IRQ line (active low) wired up so we can skip the
+    // status-byte poll while the chip has nothing for us. wrap the pin in `Irq` first,
+    // e.g. `Pn532::open_with_irq(dev, Irq(pin))`.
+    pub fn open_with_irq(dev: &str, irq: IRQ) -> Result<Self, Error<LinuxI2CError>> {
+        let i2c = I2cdev::new(dev).map_err(Error::Transport)?;
+        Ok(Self {
+            i2c,
+            address: PN532_ADDR,
+            delay: linux_embedded_hal::Delay,
+            irq,
+            retry: RetryPolicy::default(),
+        })
+    }
+}
+
+impl<I2C: I2c, D: DelayNs, IRQ: ReadyLine> Pn532<I2C, D, IRQ> {
+    // for callers constructing the bus and delay source themselves (non-Linux HALs, or a
+    // non-default I2C address).
+    pub fn new(i2c: I2C, address: u8, delay: D, irq: IRQ) -> Self {
+        Self {
+            i2c,
+            address,
+            delay,
+            irq,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    // how many times to retry a command exchange, and how long to back off between
+    // attempts (in milliseconds), before a transport hiccup or missing ack surfaces as a
+    // hard error.
+    pub fn set_retry_policy(&mut self, max_retries: u32, backoff_ms: u32) {
+        self.retry = RetryPolicy { max_retries, backoff_ms };
+    }
+
+    // a lone ACK frame (the same bytes `expect_ack` accepts as an ack), which tells the
+    // chip to cancel whatever command it's in the middle of so the next one starts
+    // clean. see UM0701-02 page 34.
+    fn abort(&mut self) -> Result<(), Error<I2C::Error>> {
+        const ABORT_FRAME: [u8; 6] = [0x00, 0x00, 0xff, 0x00, 0xff, 0x00];
+        self.i2c.write(self.address, &ABORT_FRAME)?;
+        Ok(())
+    }
+
+    // whether a failed exchange is worth retrying at all: a transport hiccup or a
+    // missing/garbled ack can clear up on its own, but a deterministic chip-reported
+    // error (a real application error, or a frame arriving out of order) will just
+    // happen again, so it should surface immediately instead of spending retries and
+    // abort frames on it.
+    fn is_retryable(e: &Error<I2C::Error>) -> bool {
+        matches!(e, Error::Transport(_) | Error::Nack | Error::Timeout)
+    }
+
+    // send `payload` and wait for its ack, retrying per `self.retry` on a transport
+    // error or a missing/garbled ack. past the first retry, abort whatever the chip
+    // might be stuck on before trying again.
+    fn send_and_ack(&mut self, payload: &[u8]) -> Result<(), Error<I2C::Error>> {
+        let mut last_err = Error::Timeout;
+        for attempt in 0..=self.retry.max_retries {
+            if attempt > 0 {
+                if attempt > 1 {
+                    let _ = self.abort();
+                }
+                self.delay.delay_ms(self.retry.backoff_ms);
+            }
+            match self.send_frame(payload).and_then(|_| self.expect_ack()) {
+                Ok(()) => return Ok(()),
+                Err(e) if !Self::is_retryable(&e) => return Err(e),
+                Err(e) => last_err = e,
+            }
+        }
+        Err(last_err)
+    }
+
+    // like `send_and_ack`, but also reads back the chip's response frame. `timeout_ms`
+    // bounds how long we'll wait for that response, in milliseconds.
+    fn exchange(&mut self, payload: &[u8], timeout_ms: u32) -> Result<Vec<u8>, Error<I2C::Error>> {
+        let mut last_err = Error::Timeout;
+        for attempt in 0..=self.retry.max_retries {
+            if attempt > 0 {
+                if attempt > 1 {
+                    let _ = self.abort();
+                }
+                self.delay.delay_ms(self.retry.backoff_ms);
+            }
+            let result = self.send_frame(payload)
+                .and_then(|_| self.expect_ack())
+                .and_then(|_| self.receive_frame(timeout_ms));
+            match result {
+                Ok(r) => return Ok(r),
+                Err(e) if !Self::is_retryable(&e) => return Err(e),
+                Err(e) => last_err = e,
+            }
+        }
+        Err(last_err)
+    }
+
+    // read transactions on the PN532 prepend a status byte whose LSB signals whether a
+    // frame is ready; this checks it once, without blocking, and returns the frame bytes
+    // that follow it when ready. see UM0701-02 page 44.
+    fn try_read_frame(&mut self) -> Result<Option<[u8; 256]>, Error<I2C::Error>> {
+        if !self.irq.is_ready() {
+            return Ok(None);
+        }
+
+        let mut status = [0u8; 1];
+        self.i2c.read(self.address, &mut status)?;
+        if status[0] & 1 == 0 {
+            return Ok(None);
+        }
+
+        let mut b = [0u8; 256];
+        self.i2c.read(self.address, &mut b)?;
+        Ok(Some(b))
+    }
 
     // information frame, see UM0701-02 page 28
-    fn send_frame(&mut self, payload: &[u8]) -> Result<(), LinuxI2CError> {
+    fn send_frame(&mut self, payload: &[u8]) -> Result<(), Error<I2C::Error>> {
         assert!(payload.len() < 0xfe);
 
         let len = payload.len() as u8 + 1;
@@ -93,14 +340,19 @@ impl Pn532 {
         b.push(checksum);
         b.push(0x00); // postamble
 
-        self.i2c.write(&b)
+        self.i2c.write(self.address, &b)?;
+        Ok(())
     }
 
-    fn expect_ack(&mut self) -> Result<(), LinuxI2CError> {
-        for _ in 0..3{
-            sleep(Duration::from_millis(1));
-            let mut b = [0u8; 256];
-            self.i2c.read(&mut b)?;
+    fn expect_ack(&mut self) -> Result<(), Error<I2C::Error>> {
+        for _ in 0..100u32 {
+            let b = match self.try_read_frame()? {
+                Some(b) => b,
+                None => {
+                    self.delay.delay_ms(1);
+                    continue;
+                }
+            };
 
             let mut state = 0;
             for i in 0..b.len() {
@@ -118,13 +370,13 @@ impl Pn532 {
                         return Ok(());
                     }
                     (0xff, 2) => {
-                        return Err(std::io::Error::new(std::io::ErrorKind::Other, "nack").into());
+                        return Err(Error::Nack);
                     }
                     (1 , 2) => {
-                        return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("application error 0x{:x}", b[i+2])).into())
+                        return Err(Error::Application(b[i+2]));
                     },
                     (_, 2) => {
-                        return Err(std::io::Error::new(std::io::ErrorKind::Other, "out of order").into());
+                        return Err(Error::OutOfOrder);
                     },
                     _ => {
                         state = 0;
@@ -132,20 +384,18 @@ impl Pn532 {
                 }
             }
         }
-        Err(std::io::Error::new(std::io::ErrorKind::Other, "timeout").into())
+        Err(Error::Timeout)
     }
 
-
-
-    fn receive_frame(&mut self, timeout: Duration) -> Result<Vec<u8>, LinuxI2CError> {
-        let now = Instant::now();
-        loop {
-            if now.elapsed() > timeout {
-                return Err(std::io::Error::new(std::io::ErrorKind::Other, "timeout").into());
-            }
-            sleep(Duration::from_millis(1));
-            let mut b = [0u8; 256];
-            self.i2c.read(&mut b)?;
+    fn receive_frame(&mut self, timeout_ms: u32) -> Result<Vec<u8>, Error<I2C::Error>> {
+        for _ in 0..timeout_ms.max(1) {
+            let b = match self.try_read_frame()? {
+                Some(b) => b,
+                None => {
+                    self.delay.delay_ms(1);
+                    continue;
+                }
+            };
 
             let mut state = 0;
             for i in 0..b.len() {
@@ -168,7 +418,7 @@ impl Pn532 {
                         break;
                     }
                     (1 , 2) => {
-                        return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("application error 0x{:x}", b[i+2])).into())
+                        return Err(Error::Application(b[i+2]));
                     },
                     (size, 2) => {
                         return Ok(b[i+3.. i+3 + (size as usize - 1)].to_vec());
@@ -179,100 +429,254 @@ impl Pn532 {
                 }
             }
         }
+        Err(Error::Timeout)
     }
 
     // ( IC version , firmware version, firmware revision, feature bitfield)
-    pub fn get_firmware_version(&mut self) -> Result<(u8,u8,u8,u8), LinuxI2CError> {
-        self.send_frame(&[Command::GetFirmwareVersion as u8])?;
-        self.expect_ack()?;
-        let r = self.receive_frame(Duration::from_millis(10))?;
+    pub fn get_firmware_version(&mut self) -> Result<(u8,u8,u8,u8), Error<I2C::Error>> {
+        let r = self.exchange(&[Command::GetFirmwareVersion as u8], 10)?;
         Ok((r[1],r[2],r[3],r[4]))
     }
 
 
-    pub fn powerdown(&mut self) -> Result<(), LinuxI2CError> {
-        self.send_frame(&[
+    pub fn powerdown(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.send_and_ack(&[
             Command::PowerDown as u8,
             0b10000011,
         ])?;
-        self.expect_ack()?;
 
         // according to page 98 remarks, we need to lock the bus for 1ms,
         // otherwise the chip might get confused
-        sleep(Duration::from_millis(1));
+        self.delay.delay_ms(1);
 
         Ok(())
     }
 
 
-    pub fn setup(&mut self) -> Result<(), LinuxI2CError> {
-        self.send_frame(&[
+    pub fn setup(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.send_and_ack(&[
             Command::SAMConfiguration as u8,
             0x01 // normal mode
-        ])?;
-        self.expect_ack()?;
-        Ok(())
+        ])
     }
 
-    pub fn list(&mut self, timeout: Duration) -> Result<Vec<Vec<u8>>, LinuxI2CError> {
-        self.send_frame(&[
-            Command::InListPassiveTarget as u8,
-            0x02, // max-targets. the chip only supposed 2, so i dunno why this is a parameter
-            CardType::IsoTypeA as u8,
-        ])?;
-        self.expect_ack()?;
-
-
-
-
-        let r = self.receive_frame(timeout)?;
+    // single-shot Type-A scan, kept for callers that only care about UIDs. `timeout_ms`
+    // is honored by deriving an `auto_poll` poll_count from it (period fixed at the
+    // chip's 150ms unit); reach for `auto_poll` directly for multi-protocol scans or the
+    // full typed target.
+    pub fn list(&mut self, timeout_ms: u32) -> Result<Vec<Vec<u8>>, Error<I2C::Error>> {
+        const PERIOD: u8 = 1; // 150ms per round
+        // 0xff is InAutoPoll's "poll forever" sentinel, not a valid round count.
+        let poll_count = (timeout_ms / (150 * PERIOD as u32)).clamp(1, 0xfe) as u8;
+
+        let targets = self.auto_poll(poll_count, PERIOD, &[CardType::IsoTypeA])?;
+        Ok(targets.into_iter().filter_map(|t| match t {
+            Target::IsoTypeA { uid, .. } => Some(uid),
+            _ => None,
+        }).collect())
+    }
 
-        if r.len() < 5 {
+    // let the chip itself cycle through the requested technologies and report whatever
+    // it finds, instead of us hand-rolling one InListPassiveTarget per protocol. see
+    // UM0701-02 page 52.
+    pub fn auto_poll(
+        &mut self,
+        poll_count: u8,
+        period: u8,
+        types: &[CardType],
+    ) -> Result<Vec<Target>, Error<I2C::Error>> {
+        let mut payload = vec![Command::InAutoPoll as u8, poll_count, period];
+        payload.extend(types.iter().map(|t| *t as u8));
+
+        // period is in units of 150ms; give the chip room for the full poll_count rounds
+        // plus some slack for the frame to come back.
+        let timeout_ms = 150 * period.max(1) as u32 * poll_count.max(1) as u32 + 500;
+        let r = self.exchange(&payload, timeout_ms)?;
+
+        if r.len() < 2 {
             return Ok(Vec::new());
         }
 
         let num = r[1];
         let mut i = 2;
+        let mut targets = Vec::new();
 
-        let mut tags = Vec::new();
         for _ in 0..num {
-            if i >= r.len() {
-                return Ok(Vec::new());
-            }
-            i   += 1 // note that the spec is confusingly missing a one byte enumerator prefix
-                +  2 // sens_res
-                +  1 // sel_res
-            ;
-
-            if i >= r.len() {
-                return Ok(Vec::new());
-            }
-            let len     = r[i] as  usize;
-            i += 1;
-            if i >= r.len() {
-                return Ok(Vec::new());
+            if i + 1 >= r.len() {
+                break;
             }
-            if i + len  > r.len() {
-                return Ok(Vec::new());
+            let card_type = r[i];
+            let len = r[i + 1] as usize;
+            i += 2;
+            if i + len > r.len() {
+                break;
             }
-            tags.push(r[i .. i + len].to_vec());
+            let data = &r[i .. i + len];
             i += len;
 
-            //ats
-            if i < r.len() {
-                let len = r[i] as  usize;
-                // the chip doesn't tell us if there's an ats field, it just emits one or not.
-                // in this case the the ats length field will be the index 2, which is also not a valid ats size,
-                if len == 2 {
-                    continue;
+            // data[0] is the logical target number (Tg); callers address targets by
+            // position in the returned Vec instead, same as `list()` always has.
+            let data = if data.len() > 1 { &data[1..] } else { &[] };
+
+            let target = match card_type {
+                t if t == CardType::IsoTypeA as u8 => {
+                    if data.len() < 4 {
+                        continue;
+                    }
+                    let sens_res = [data[0], data[1]];
+                    let sel_res = data[2];
+                    let uid_len = data[3] as usize;
+                    if 4 + uid_len > data.len() {
+                        continue;
+                    }
+                    let uid = data[4 .. 4 + uid_len].to_vec();
+                    let rest = &data[4 + uid_len ..];
+                    // the chip only emits an ATS field some of the time;
+                    // a length of 2 there is not a valid ATS so treat it as absent.
+                    let ats = if !rest.is_empty() && rest[0] != 2 {
+                        Some(rest[1..].to_vec())
+                    } else {
+                        None
+                    };
+                    Target::IsoTypeA { sens_res, sel_res, uid, ats }
                 }
-                i += len;
-            }
+                t if t == CardType::IsoTypeB as u8 => Target::IsoTypeB { atqb: data.to_vec() },
+                t if t == CardType::FeliCa212 as u8 => Target::FeliCa212 { id: data.to_vec() },
+                t if t == CardType::FeliCa424 as u8 => Target::FeliCa424 { id: data.to_vec() },
+                t if t == CardType::Jewel as u8 => {
+                    if data.len() < 6 {
+                        continue;
+                    }
+                    Target::Jewel {
+                        sens_res: [data[0], data[1]],
+                        uid: data[2..6].to_vec(),
+                    }
+                }
+                _ => continue,
+            };
+            targets.push(target);
         }
 
-        Ok(tags)
+        Ok(targets)
+    }
+
+    // put the chip into target mode so it can be addressed by an initiator instead of
+    // addressing tags itself, see UM0701-02 page 70.
+    // returns the mode byte the chip settled on and the first command frame sent by the
+    // initiator, if one arrived before the chip finished initializing.
+    pub fn init_as_target(
+        &mut self,
+        mode: u8,
+        mifare_params: [u8; 6],
+        felica_params: [u8; 18],
+        nfcid3t: [u8; 10],
+        general_bytes: &[u8],
+        historical_bytes: &[u8],
+    ) -> Result<(u8, Vec<u8>), Error<I2C::Error>> {
+        // cmd + mode + mifare_params + felica_params + nfcid3t + two length prefixes,
+        // plus the two byte slices themselves, all has to fit in one information frame.
+        let frame_len = 2 + 6 + 18 + 10 + 1 + general_bytes.len() + 1 + historical_bytes.len();
+        if frame_len >= 0xfe {
+            return Err(Error::PayloadTooLong);
+        }
+
+        let mut payload = vec![Command::TgInitAsTarget as u8, mode];
+        payload.extend_from_slice(&mifare_params);
+        payload.extend_from_slice(&felica_params);
+        payload.extend_from_slice(&nfcid3t);
+        payload.push(general_bytes.len() as u8);
+        payload.extend_from_slice(general_bytes);
+        payload.push(historical_bytes.len() as u8);
+        payload.extend_from_slice(historical_bytes);
+
+        let r = self.exchange(&payload, 30_000)?;
+        if r.len() < 2 {
+            return Ok((0, Vec::new()));
+        }
+        Ok((r[1], r[2..].to_vec()))
+    }
+
+    // fetch the command the initiator most recently sent to us while in target mode,
+    // see UM0701-02 page 80.
+    pub fn tg_get_data(&mut self) -> Result<(u8, Vec<u8>), Error<I2C::Error>> {
+        let r = self.exchange(&[Command::TgGetData as u8], 10)?;
+        if r.len() < 2 {
+            return Ok((0, Vec::new()));
+        }
+        Ok((r[1], r[2..].to_vec()))
+    }
+
+    // answer the initiator's last command while in target mode, see UM0701-02 page 81.
+    pub fn tg_set_data(&mut self, data: &[u8]) -> Result<(), Error<I2C::Error>> {
+        let mut payload = vec![Command::TgSetData as u8];
+        payload.extend_from_slice(data);
+
+        let r = self.exchange(&payload, 10)?;
+        if r.len() > 1 && r[1] != 0 {
+            return Err(Error::Application(r[1]));
+        }
+        Ok(())
     }
-}
 
+    // exchange an APDU with a previously listed target, see UM0701-02 page 51.
+    // returns the chip's status byte followed by whatever the target sent back.
+    pub fn in_data_exchange(&mut self, target: u8, data_out: &[u8]) -> Result<(u8, Vec<u8>), Error<I2C::Error>> {
+        let mut payload = vec![Command::InDataExchange as u8, target];
+        payload.extend_from_slice(data_out);
 
+        let r = self.exchange(&payload, 100)?;
+        if r.len() < 2 {
+            return Err(Error::ShortResponse);
+        }
+        Ok((r[1], r[2..].to_vec()))
+    }
+
+    // authenticate a MIFARE Classic sector with KeyA (0x60) or KeyB (0x61) before any
+    // read/write of its blocks, see MIFARE Classic EV1 datasheet section 8.6.
+    pub fn mifare_auth(
+        &mut self,
+        target: u8,
+        block: u8,
+        key_type: u8,
+        key: [u8; 6],
+        uid: &[u8],
+    ) -> Result<(), Error<I2C::Error>> {
+        let mut data_out = vec![key_type, block];
+        data_out.extend_from_slice(&key);
+        data_out.extend_from_slice(uid);
+
+        let (status, _) = self.in_data_exchange(target, &data_out)?;
+        if status != 0 {
+            return Err(Error::Application(status));
+        }
+        Ok(())
+    }
 
+    // read a 16 byte MIFARE Classic block; the sector must already be authenticated
+    // with `mifare_auth`.
+    pub fn mifare_read(&mut self, target: u8, block: u8) -> Result<[u8; 16], Error<I2C::Error>> {
+        let (status, data) = self.in_data_exchange(target, &[0x30, block])?;
+        if status != 0 {
+            return Err(Error::Application(status));
+        }
+        if data.len() < 16 {
+            return Err(Error::ShortResponse);
+        }
+        let mut out = [0u8; 16];
+        out.copy_from_slice(&data[..16]);
+        Ok(out)
+    }
+
+    // write a 16 byte MIFARE Classic block; the sector must already be authenticated
+    // with `mifare_auth`.
+    pub fn mifare_write(&mut self, target: u8, block: u8, data: [u8; 16]) -> Result<(), Error<I2C::Error>> {
+        let mut data_out = vec![0xA0, block];
+        data_out.extend_from_slice(&data);
+
+        let (status, _) = self.in_data_exchange(target, &data_out)?;
+        if status != 0 {
+            return Err(Error::Application(status));
+        }
+        Ok(())
+    }
+}